@@ -1,4 +1,6 @@
-use super::{PositionIterInternal, PyGenericAlias, PyTupleRef, PyType, PyTypeRef};
+use super::{
+    PositionIterInternal, PyFloat, PyGenericAlias, PyInt, PyStr, PyTupleRef, PyType, PyTypeRef,
+};
 use crate::atomic_func;
 use crate::common::lock::{
     PyMappedRwLockReadGuard, PyMutex, PyRwLock, PyRwLockReadGuard, PyRwLockWriteGuard,
@@ -20,7 +22,7 @@ use crate::{
     utils::collection_repr,
     vm::VirtualMachine,
 };
-use std::{fmt, ops::DerefMut};
+use std::{cmp::Ordering, fmt, ops::DerefMut};
 
 #[pyclass(module = false, name = "list", unhashable = true, traverse)]
 #[derive(Default)]
@@ -118,7 +120,7 @@ impl PyList {
 
     #[pymethod]
     pub(crate) fn extend(&self, x: PyObjectRef, vm: &VirtualMachine) -> PyResult<()> {
-        let mut new_elements = x.try_to_value(vm)?;
+        let mut new_elements = extract_cloned(&x, Ok, vm)?;
         self.borrow_vec_mut().append(&mut new_elements);
         Ok(())
     }
@@ -320,11 +322,23 @@ impl PyList {
 
     #[pymethod]
     pub(crate) fn sort(&self, options: SortOptions, vm: &VirtualMachine) -> PyResult<()> {
-        // replace list contents with [] for duration of sort.
-        // this prevents keyfunc from messing with the list and makes it easy to
-        // check if it tries to append elements to it.
+        self.with_taken_elements(vm, |elements| {
+            do_sort(vm, elements, options.key, options.reverse)
+        })
+    }
+
+    /// Runs `f` against the list's elements with the "modified during sort"
+    /// protocol: the elements are swapped out for an empty `Vec` for the
+    /// duration of `f` - this prevents a Python-level key function (or, for
+    /// the Rust entry points below, a careless comparator) from observing or
+    /// mutating the list mid-sort, and makes it easy to detect if it tried
+    /// to append elements to it anyway.
+    fn with_taken_elements<F>(&self, vm: &VirtualMachine, f: F) -> PyResult<()>
+    where
+        F: FnOnce(&mut Vec<PyObjectRef>) -> PyResult<()>,
+    {
         let mut elements = std::mem::take(self.borrow_vec_mut().deref_mut());
-        let res = do_sort(vm, &mut elements, options.key, options.reverse);
+        let res = f(&mut elements);
         std::mem::swap(self.borrow_vec_mut().deref_mut(), &mut elements);
         res?;
 
@@ -335,6 +349,39 @@ impl PyList {
         Ok(())
     }
 
+    /// Sort the list in place with a native Rust comparator, without
+    /// constructing a Python callable. The comparator is fallible because it
+    /// may still need to go back through the VM (e.g. to call into the
+    /// objects being compared), so errors propagate instead of forcing the
+    /// caller to panic or `.unwrap()`. Keeps the same stable,
+    /// mutation-detecting protocol as the `sort` pymethod, so this is safe
+    /// to call even while other code may be holding a reference to the list.
+    pub fn sort_by<F>(&self, vm: &VirtualMachine, mut compare: F) -> PyResult<()>
+    where
+        F: FnMut(&PyObjectRef, &PyObjectRef) -> PyResult<Ordering>,
+    {
+        self.with_taken_elements(vm, |elements| {
+            timsort::try_sort_by_gt(elements, |a, b| Ok(compare(a, b)? == Ordering::Greater))
+        })
+    }
+
+    /// Like [`PyList::sort_by`], but compares a key derived from each
+    /// element rather than the elements themselves. The key is computed
+    /// once per element up front and reused for every comparison, the same
+    /// caching the `sort` pymethod does for a Python `key=` function.
+    pub fn sort_by_key<K, T>(&self, vm: &VirtualMachine, mut key: K) -> PyResult<()>
+    where
+        K: FnMut(&PyObjectRef) -> T,
+        T: Ord,
+    {
+        self.with_taken_elements(vm, |elements| {
+            let mut items: Vec<_> = elements.drain(..).map(|x| (key(&x), x)).collect();
+            timsort::try_sort_by_gt(&mut items, |a, b| Ok(a.0 > b.0))?;
+            *elements = items.into_iter().map(|(_, val)| val).collect();
+            Ok(())
+        })
+    }
+
     #[pyclassmethod]
     fn __class_getitem__(cls: PyTypeRef, args: PyObjectRef, vm: &VirtualMachine) -> PyGenericAlias {
         PyGenericAlias::from_args(cls, args, vm)
@@ -352,8 +399,8 @@ where
         list.borrow_vec().iter().map(|x| f(x.clone())).collect()
     } else {
         let iter = obj.to_owned().get_iter(vm)?;
+        let len = length_hint(obj, iter.as_object(), vm)?;
         let iter = iter.iter::<PyObjectRef>(vm)?;
-        let len = obj.to_sequence().length_opt(vm).transpose()?.unwrap_or(0);
         let mut v = Vec::with_capacity(len);
         for x in iter {
             v.push(f(x?)?);
@@ -363,6 +410,23 @@ where
     }
 }
 
+/// Capacity hint for draining `obj` into a freshly allocated `Vec`: prefer
+/// the sequence protocol's length, falling back to the iterator's
+/// `__length_hint__`. Clamped so a bogus or adversarial `__length_hint__`
+/// can't force a huge reservation. Only the "this object doesn't support
+/// `__len__`/`__length_hint__`" case is treated as "no hint" - an exception
+/// actually raised by either one still propagates, the same as it did
+/// through `to_sequence().length_opt(vm).transpose()?` before this helper
+/// existed.
+fn length_hint(obj: &PyObject, iter: &PyObject, vm: &VirtualMachine) -> PyResult<usize> {
+    const MAX_PREALLOCATE: usize = 1_000_000;
+    let hint = match obj.to_sequence().length_opt(vm) {
+        Some(len) => len?,
+        None => iter.length_hint(vm, 0)?,
+    };
+    Ok(hint.min(MAX_PREALLOCATE))
+}
+
 impl MutObjectSequenceOp for PyList {
     type Inner = [PyObjectRef];
 
@@ -388,7 +452,7 @@ impl Initializer for PyList {
 
     fn init(zelf: PyRef<Self>, iterable: Self::Args, vm: &VirtualMachine) -> PyResult<()> {
         let mut elements = if let OptionalArg::Present(iterable) = iterable {
-            iterable.try_to_value(vm)?
+            extract_cloned(&iterable, Ok, vm)?
         } else {
             vec![]
         };
@@ -503,6 +567,60 @@ impl Representable for PyList {
     }
 }
 
+/// The concrete native type that every key in a homogeneous sort shares,
+/// letting `do_sort` bypass `rich_compare_bool` entirely.
+#[derive(Clone, Copy)]
+enum NativeKeyKind {
+    Int,
+    Str,
+    Float,
+}
+
+impl NativeKeyKind {
+    /// If every key is a plain (non-subclassed) int, str, or float - and, for
+    /// floats, none of them are NaN - return the shared kind. `rich_compare_bool`
+    /// is the only thing that knows about subclass `__lt__` overrides, so a
+    /// single non-exact instance forces the generic path. Takes an iterator
+    /// rather than a slice so the caller doesn't have to clone every key
+    /// (bumping an `Arc` refcount per element) just to let this function look
+    /// at them.
+    fn of<'a>(keys: impl Iterator<Item = &'a PyObjectRef> + Clone, vm: &VirtualMachine) -> Option<Self> {
+        let first = keys.clone().next()?;
+        let kind = if first.downcast_ref_if_exact::<PyInt>(vm).is_some() {
+            Self::Int
+        } else if first.downcast_ref_if_exact::<PyStr>(vm).is_some() {
+            Self::Str
+        } else if first.downcast_ref_if_exact::<PyFloat>(vm).is_some() {
+            Self::Float
+        } else {
+            return None;
+        };
+        let homogeneous = keys.into_iter().all(|k| match kind {
+            Self::Int => k.downcast_ref_if_exact::<PyInt>(vm).is_some(),
+            Self::Str => k.downcast_ref_if_exact::<PyStr>(vm).is_some(),
+            Self::Float => k
+                .downcast_ref_if_exact::<PyFloat>(vm)
+                .is_some_and(|f| !f.to_f64().is_nan()),
+        });
+        homogeneous.then_some(kind)
+    }
+
+    /// `a > b`, comparing the extracted native values directly.
+    fn gt(self, a: &PyObjectRef, b: &PyObjectRef) -> bool {
+        match self {
+            Self::Int => {
+                a.downcast_ref::<PyInt>().unwrap().as_bigint() > b.downcast_ref::<PyInt>().unwrap().as_bigint()
+            }
+            Self::Str => {
+                a.downcast_ref::<PyStr>().unwrap().as_str() > b.downcast_ref::<PyStr>().unwrap().as_str()
+            }
+            Self::Float => {
+                a.downcast_ref::<PyFloat>().unwrap().to_f64() > b.downcast_ref::<PyFloat>().unwrap().to_f64()
+            }
+        }
+    }
+}
+
 fn do_sort(
     vm: &VirtualMachine,
     values: &mut Vec<PyObjectRef>,
@@ -521,10 +639,37 @@ fn do_sort(
             .iter()
             .map(|x| Ok((x.clone(), key_func.call((x.clone(),), vm)?)))
             .collect::<Result<Vec<_>, _>>()?;
-        timsort::try_sort_by_gt(&mut items, |a, b| cmp(&a.1, &b.1))?;
+        // CPython's listsort optimization: if every computed key is the same
+        // concrete int/str/float type, compare the native values directly
+        // instead of going through `rich_compare_bool` for every pair.
+        let kind = (items.len() > 1)
+            .then(|| NativeKeyKind::of(items.iter().map(|(_, k)| k), vm))
+            .flatten();
+        if let Some(kind) = kind {
+            let gt = |a: &(PyObjectRef, PyObjectRef), b: &(PyObjectRef, PyObjectRef)| {
+                Ok(if reverse {
+                    kind.gt(&b.1, &a.1)
+                } else {
+                    kind.gt(&a.1, &b.1)
+                })
+            };
+            timsort::try_sort_by_gt(&mut items, gt)?;
+        } else {
+            timsort::try_sort_by_gt(&mut items, |a, b| cmp(&a.1, &b.1))?;
+        }
         *values = items.into_iter().map(|(val, _)| val).collect();
     } else {
-        timsort::try_sort_by_gt(values, cmp)?;
+        let kind = (values.len() > 1)
+            .then(|| NativeKeyKind::of(values.iter(), vm))
+            .flatten();
+        if let Some(kind) = kind {
+            let gt = |a: &PyObjectRef, b: &PyObjectRef| {
+                Ok(if reverse { kind.gt(b, a) } else { kind.gt(a, b) })
+            };
+            timsort::try_sort_by_gt(values, gt)?;
+        } else {
+            timsort::try_sort_by_gt(values, cmp)?;
+        }
     }
 
     Ok(())
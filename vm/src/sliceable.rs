@@ -0,0 +1,465 @@
+//! Shared helpers for resolving Python slice/index subscripts against
+//! concrete Rust containers (currently used by `list`, and intended for
+//! any other sequence type that wants the same negative-index/slice
+//! semantics without re-deriving them).
+
+use crate::{
+    AsObject, PyObject, PyResult, VirtualMachine,
+    builtins::{PyInt, PySlice},
+};
+use num_traits::{Signed, ToPrimitive};
+
+#[derive(Debug, Clone, Copy)]
+pub struct SaturatedSlice {
+    start: isize,
+    stop: isize,
+    step: isize,
+}
+
+impl SaturatedSlice {
+    /// Convert a `PySlice` into contained values, saturating boundary values
+    /// to `isize::MIN` or `isize::MAX` so later arithmetic against a concrete
+    /// length can't overflow.
+    pub fn with_slice(slice: &PySlice, vm: &VirtualMachine) -> PyResult<Self> {
+        let step = Self::resolve_step(slice.step.as_deref(), vm)?;
+        let negative_step = step.is_negative();
+        // Python represents an omitted bound either as the Rust-level
+        // `None` (`start`, via `slice()`'s optional positional arg) or as
+        // the Python `None` singleton (always true for `stop`, since
+        // `PySlice` stores it unconditionally) - `resolve_bound` treats
+        // anything that isn't a concrete int as "omitted" either way, so
+        // `a[1:]` / `a[:]` fall through to the same open-ended defaults as
+        // a genuinely absent `start`.
+        let start = Self::resolve_bound(slice.start.as_deref(), isize::MAX, isize::MIN, negative_step);
+        let stop = Self::resolve_bound(Some(&slice.stop), isize::MIN, isize::MAX, negative_step);
+        Ok(Self { start, stop, step })
+    }
+
+    fn resolve_step(step: Option<&PyObject>, vm: &VirtualMachine) -> PyResult<isize> {
+        let step = match step.and_then(|s| s.downcast_ref::<PyInt>()) {
+            Some(i) => i.as_bigint().to_isize().unwrap_or_else(|| {
+                if i.as_bigint().is_negative() {
+                    isize::MIN
+                } else {
+                    isize::MAX
+                }
+            }),
+            None => 1,
+        };
+        if step == 0 {
+            return Err(vm.new_value_error("slice step cannot be zero"));
+        }
+        Ok(step)
+    }
+
+    fn resolve_bound(
+        value: Option<&PyObject>,
+        default_if_negative_step: isize,
+        default_if_positive_step: isize,
+        negative_step: bool,
+    ) -> isize {
+        match value.and_then(|v| v.downcast_ref::<PyInt>()) {
+            Some(int) => int.as_bigint().to_isize().unwrap_or_else(|| {
+                if int.as_bigint().is_negative() {
+                    isize::MIN
+                } else {
+                    isize::MAX
+                }
+            }),
+            None if negative_step => default_if_negative_step,
+            None => default_if_positive_step,
+        }
+    }
+
+    /// Build a slice directly from already-resolved bounds, for callers that
+    /// didn't go through a Python `slice` object (and so have no `vm` handy
+    /// to raise a `ValueError` through). Unlike `with_slice`, there's no
+    /// Python exception to raise here if `step == 0` - it's a programmer
+    /// error in the caller, not a value that came from user code - so this
+    /// asserts instead of silently building a `SaturatedSlice` that would
+    /// panic on its first division the moment `.indices(len)` is called.
+    ///
+    /// # Panics
+    /// Panics if `step == 0`.
+    pub fn with_index(start: isize, stop: isize, step: isize) -> Self {
+        assert_ne!(step, 0, "slice step cannot be zero");
+        Self { start, stop, step }
+    }
+
+    /// Clamp `start`/`stop`/`step` against a concrete container length,
+    /// returning Python's usual `(start, stop, step)` triple. Unlike
+    /// `range`, `stop` for a negative step can legitimately need to refer to
+    /// "one before index 0", so this intentionally stays in `isize` rather
+    /// than converting to `usize` here - see `SliceIndices::new`, which is
+    /// the only place that needs an actual element count from these.
+    pub fn adjust_indices(&self, len: usize) -> (isize, isize, isize) {
+        let len = len as isize;
+        if self.step.is_negative() {
+            let clamp = |value: isize| {
+                if value < 0 {
+                    (value + len).clamp(-1, len - 1)
+                } else {
+                    value.clamp(-1, len - 1)
+                }
+            };
+            (clamp(self.start), clamp(self.stop), self.step)
+        } else {
+            let clamp = |value: isize| {
+                if value < 0 {
+                    (value + len).clamp(0, len)
+                } else {
+                    value.clamp(0, len)
+                }
+            };
+            (clamp(self.start), clamp(self.stop), self.step)
+        }
+    }
+
+    /// Resolve this slice against `len`, yielding the concrete positions it
+    /// selects, in traversal order (reversed when `step` is negative).
+    pub fn indices(&self, len: usize) -> SliceIndices {
+        SliceIndices::new(self, len)
+    }
+}
+
+/// An [`ExactSizeIterator`] + [`DoubleEndedIterator`] over the concrete
+/// `usize` positions a resolved slice selects out of a container of a given
+/// length. Centralizing this avoids every `getitem_by_slice` /
+/// `setitem_by_slice` / `delitem_by_slice` call site re-deriving start/stop
+/// clamping and step handling by hand.
+#[derive(Debug, Clone)]
+pub struct SliceIndices {
+    front: isize,
+    step: isize,
+    remaining: usize,
+}
+
+impl SliceIndices {
+    fn new(slice: &SaturatedSlice, len: usize) -> Self {
+        let (start, stop, step) = slice.adjust_indices(len);
+        // Same element-count formula CPython's `PySlice_AdjustIndices` uses:
+        // ceil((stop - start) / step) for a forward slice, with the sign
+        // flipped for a backward one. `stop` for a negative step is already
+        // the logical boundary (e.g. `-1` meaning "down to and including
+        // index 0") - no further shift is needed here.
+        let remaining = if step > 0 {
+            if stop <= start {
+                0
+            } else {
+                ((stop - start - 1) / step + 1) as usize
+            }
+        } else if stop >= start {
+            0
+        } else {
+            ((stop - start + 1) / step + 1) as usize
+        };
+        Self {
+            front: start,
+            step,
+            remaining,
+        }
+    }
+}
+
+impl Iterator for SliceIndices {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let i = self.front;
+        self.front += self.step;
+        self.remaining -= 1;
+        Some(i as usize)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for SliceIndices {
+    fn len(&self) -> usize {
+        self.remaining
+    }
+}
+
+impl DoubleEndedIterator for SliceIndices {
+    fn next_back(&mut self) -> Option<usize> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let i = self.front + self.step * self.remaining as isize;
+        Some(i as usize)
+    }
+}
+
+pub enum SequenceIndex {
+    Int(isize),
+    Slice(SaturatedSlice),
+}
+
+impl SequenceIndex {
+    pub fn try_from_borrowed_object(
+        vm: &VirtualMachine,
+        obj: &PyObject,
+        owner_type: &str,
+    ) -> PyResult<Self> {
+        if let Some(i) = obj.downcast_ref::<PyInt>() {
+            Ok(Self::Int(i.try_to_primitive(vm)?))
+        } else if let Some(slice) = obj.downcast_ref::<PySlice>() {
+            SaturatedSlice::with_slice(slice, vm).map(Self::Slice)
+        } else {
+            Err(vm.new_type_error(format!(
+                "{} indices must be integers or slices, not {}",
+                owner_type,
+                obj.class().name()
+            )))
+        }
+    }
+}
+
+pub trait SliceableSequenceOp {
+    type Item: Clone;
+
+    fn do_get(&self, index: usize) -> Option<&Self::Item>;
+
+    fn as_slice(&self) -> &[Self::Item];
+
+    fn saturate_index(&self, index: isize) -> usize {
+        let len = self.as_slice().len() as isize;
+        if index < 0 { (index + len).max(0) } else { index.min(len) }.try_into().unwrap()
+    }
+
+    fn wrap_index(&self, index: isize) -> Option<usize> {
+        let len = self.as_slice().len() as isize;
+        let index = if index < 0 { index + len } else { index };
+        (index >= 0 && index < len).then_some(index as usize)
+    }
+
+    fn getitem_by_index(&self, vm: &VirtualMachine, index: isize) -> PyResult<Self::Item> {
+        self.wrap_index(index)
+            .and_then(|index| self.do_get(index).cloned())
+            .ok_or_else(|| vm.new_index_error("index out of range"))
+    }
+
+    fn getitem_by_slice(
+        &self,
+        _vm: &VirtualMachine,
+        slice: SaturatedSlice,
+    ) -> PyResult<Vec<Self::Item>> {
+        let elements = self.as_slice();
+        Ok(slice
+            .indices(elements.len())
+            .map(|i| elements[i].clone())
+            .collect())
+    }
+}
+
+pub trait SliceableSequenceMutOp: SliceableSequenceOp {
+    fn do_set(&mut self, index: usize, value: Self::Item);
+
+    fn as_mut_vec(&mut self) -> &mut Vec<Self::Item>;
+
+    fn setitem_by_index(&mut self, vm: &VirtualMachine, index: isize, value: Self::Item) -> PyResult<()> {
+        let index = self
+            .wrap_index(index)
+            .ok_or_else(|| vm.new_index_error("assignment index out of range"))?;
+        self.do_set(index, value);
+        Ok(())
+    }
+
+    fn setitem_by_slice(
+        &mut self,
+        vm: &VirtualMachine,
+        slice: SaturatedSlice,
+        items: &[Self::Item],
+    ) -> PyResult<()> {
+        let len = self.as_slice().len();
+        if slice.step == 1 {
+            // Derive start/stop from `adjust_indices` rather than from the
+            // slice's (possibly empty) collected indices: an empty
+            // selection still has a real insertion point (e.g.
+            // `a[5:5] = [x]` inserts at 5), which `first()`/`last()` on an
+            // empty `Vec` can't express. Matches CPython's `list_ass_slice`,
+            // which clamps `ihigh` up to `ilow` when the range is empty.
+            let (start, stop, _) = slice.adjust_indices(len);
+            let start = start as usize;
+            let stop = stop.max(start as isize) as usize;
+            self.as_mut_vec()
+                .splice(start..stop, items.iter().cloned());
+        } else {
+            let indices: Vec<usize> = slice.indices(len).collect();
+            if indices.len() != items.len() {
+                return Err(vm.new_value_error(format!(
+                    "attempt to assign sequence of size {} to extended slice of size {}",
+                    items.len(),
+                    indices.len()
+                )));
+            }
+            for (i, item) in indices.into_iter().zip(items.iter().cloned()) {
+                self.do_set(i, item);
+            }
+        }
+        Ok(())
+    }
+
+    fn delitem_by_index(&mut self, vm: &VirtualMachine, index: isize) -> PyResult<()> {
+        let index = self
+            .wrap_index(index)
+            .ok_or_else(|| vm.new_index_error("assignment index out of range"))?;
+        self.as_mut_vec().remove(index);
+        Ok(())
+    }
+
+    fn delitem_by_slice(&mut self, _vm: &VirtualMachine, slice: SaturatedSlice) -> PyResult<()> {
+        let len = self.as_slice().len();
+        let mut indices: Vec<usize> = slice.indices(len).collect();
+        // remove from back to front so earlier indices stay valid
+        indices.sort_unstable_by(|a, b| b.cmp(a));
+        let vec = self.as_mut_vec();
+        for i in indices {
+            vec.remove(i);
+        }
+        Ok(())
+    }
+}
+
+impl<T: Clone> SliceableSequenceOp for [T] {
+    type Item = T;
+
+    fn do_get(&self, index: usize) -> Option<&T> {
+        self.get(index)
+    }
+
+    fn as_slice(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T: Clone> SliceableSequenceOp for Vec<T> {
+    type Item = T;
+
+    fn do_get(&self, index: usize) -> Option<&T> {
+        self.as_slice().get(index)
+    }
+
+    fn as_slice(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T: Clone> SliceableSequenceMutOp for Vec<T> {
+    fn do_set(&mut self, index: usize, value: T) {
+        self[index] = value;
+    }
+
+    fn as_mut_vec(&mut self) -> &mut Vec<T> {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slice(start: isize, stop: isize, step: isize) -> SaturatedSlice {
+        SaturatedSlice::with_index(start, stop, step)
+    }
+
+    #[test]
+    fn forward_full_range() {
+        let s = slice(0, 10, 1);
+        assert_eq!(s.indices(5).collect::<Vec<_>>(), vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn forward_with_step() {
+        let s = slice(0, 10, 2);
+        assert_eq!(s.indices(5).collect::<Vec<_>>(), vec![0, 2, 4]);
+    }
+
+    #[test]
+    fn negative_step_reverses() {
+        let s = slice(-1, isize::MIN, -1);
+        assert_eq!(s.indices(5).collect::<Vec<_>>(), vec![4, 3, 2, 1, 0]);
+    }
+
+    #[test]
+    fn exact_size_matches_count() {
+        let s = slice(1, 9, 2);
+        let it = s.indices(10);
+        assert_eq!(it.len(), it.count());
+    }
+
+    #[test]
+    fn double_ended_matches_reversed_forward() {
+        let s = slice(0, 10, 3);
+        let forward: Vec<_> = s.indices(10).collect();
+        let mut backward: Vec<_> = s.indices(10).rev().collect();
+        backward.reverse();
+        assert_eq!(forward, backward);
+    }
+
+    #[test]
+    fn sentinel_bounds_act_as_open_ended() {
+        // isize::MAX/MIN are what `SaturatedSlice::with_slice` produces for
+        // an omitted bound; make sure `indices` treats them as "all the way
+        // to the end" rather than overflowing.
+        let forward = slice(0, isize::MAX, 1);
+        assert_eq!(forward.indices(5).collect::<Vec<_>>(), vec![0, 1, 2, 3, 4]);
+
+        let backward = slice(isize::MAX, isize::MIN, -1);
+        assert_eq!(backward.indices(5).collect::<Vec<_>>(), vec![4, 3, 2, 1, 0]);
+    }
+
+    /// Walks each slice position-by-position instead of using the
+    /// closed-form `remaining` count, as an independent cross-check of the
+    /// formula in `SliceIndices::new` across a wide sweep of bounds/steps
+    /// (this is what caught the original off-by-one on negative steps).
+    fn brute_force_indices(start: isize, stop: isize, step: isize, len: usize) -> Vec<usize> {
+        let len = len as isize;
+        let (start, stop) = if step > 0 {
+            let clamp = |v: isize| if v < 0 { (v + len).max(0) } else { v.min(len) };
+            (clamp(start), clamp(stop))
+        } else {
+            let clamp = |v: isize| if v < 0 { (v + len).max(-1) } else { v.min(len - 1) };
+            (clamp(start), clamp(stop))
+        };
+        let mut out = Vec::new();
+        let mut i = start;
+        if step > 0 {
+            while i < stop {
+                out.push(i as usize);
+                i += step;
+            }
+        } else {
+            while i > stop {
+                out.push(i as usize);
+                i += step;
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn matches_brute_force_over_many_slices() {
+        for len in 0..6usize {
+            for start in -8..8isize {
+                for stop in -8..8isize {
+                    for step in (-3..=3isize).filter(|&s| s != 0) {
+                        let s = slice(start, stop, step);
+                        let got: Vec<_> = s.indices(len).collect();
+                        let expected = brute_force_indices(start, stop, step, len);
+                        assert_eq!(
+                            got, expected,
+                            "len={len} start={start} stop={stop} step={step}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+}